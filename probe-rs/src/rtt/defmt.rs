@@ -0,0 +1,371 @@
+//! Decoding `defmt` log frames streamed over an RTT up channel.
+//!
+//! `defmt` firmware writes a compact binary encoding of each log call: a
+//! leb128-encoded index into a table of interned format strings (recovered from
+//! the `.defmt` ELF section), a leb128-encoded timestamp, then the arguments
+//! (leb128 for integers and `str` lengths, raw bytes otherwise). This module
+//! reassembles those frames from the raw bytes an [`UpChannel`] yields and turns
+//! them into [`DefmtRecord`]s using a caller-supplied [`DefmtTable`].
+
+use crate::rtt::UpChannel;
+use crate::{Core, Error};
+use anyhow::anyhow;
+
+/// The interned format strings recovered from a target ELF's `.defmt` section,
+/// indexed by the symbol address `defmt` assigned them.
+#[derive(Debug, Clone)]
+pub struct DefmtTable {
+    entries: Vec<(u64, DefmtTableEntry)>,
+}
+
+/// A single interned log statement: its format string and the severity it was
+/// logged at.
+#[derive(Debug, Clone)]
+struct DefmtTableEntry {
+    level: Option<DefmtLevel>,
+    format: String,
+}
+
+impl DefmtTable {
+    /// Builds a table from `(symbol_address, level, format_string)` triples, as
+    /// recovered from the `.defmt` section and symbol table of the target ELF.
+    pub fn new(entries: impl IntoIterator<Item = (u64, Option<DefmtLevel>, String)>) -> DefmtTable {
+        DefmtTable {
+            entries: entries
+                .into_iter()
+                .map(|(addr, level, format)| (addr, DefmtTableEntry { level, format }))
+                .collect(),
+        }
+    }
+
+    fn lookup(&self, index: u64) -> Option<&DefmtTableEntry> {
+        self.entries
+            .iter()
+            .find(|(addr, _)| *addr == index)
+            .map(|(_, entry)| entry)
+    }
+}
+
+/// Log severity, mirroring the levels `defmt::Logger` can be called with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefmtLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A single decoded `defmt` log entry.
+#[derive(Debug, Clone)]
+pub struct DefmtRecord {
+    pub level: Option<DefmtLevel>,
+    pub timestamp: u64,
+    pub message: String,
+}
+
+/// Reassembles `defmt` frames from raw bytes read off an RTT up channel and
+/// decodes them against a [`DefmtTable`].
+///
+/// Frames can arrive split across reads, so bytes read that don't yet form a
+/// complete frame are buffered until the next [`DefmtDecoder::poll`] call.
+#[derive(Debug)]
+pub struct DefmtDecoder {
+    table: DefmtTable,
+    buffer: Vec<u8>,
+}
+
+impl DefmtDecoder {
+    /// Creates a decoder for frames encoded against `table`.
+    pub fn new(table: DefmtTable) -> DefmtDecoder {
+        DefmtDecoder {
+            table,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Reads any newly available bytes from `channel` and returns every
+    /// complete `defmt` frame that can now be decoded.
+    ///
+    /// Bytes that don't form a recognized frame (an unknown table index, or a
+    /// format string whose declared arguments don't fit the ones the target
+    /// sent) are logged and dropped one at a time so the stream resynchronizes
+    /// on the next valid frame, rather than wedging the decoder forever on the
+    /// same bad prefix.
+    pub fn poll(&mut self, core: &mut Core, channel: &mut UpChannel) -> Result<Vec<DefmtRecord>, Error> {
+        let mut read_buf = [0u8; 256];
+        loop {
+            let bytes_read = channel.read(core, &mut read_buf)?;
+            if bytes_read == 0 {
+                break;
+            }
+            self.buffer.extend_from_slice(&read_buf[..bytes_read]);
+        }
+
+        let mut records = Vec::new();
+        let mut consumed = 0;
+
+        while consumed < self.buffer.len() {
+            match self.decode_frame(&self.buffer[consumed..]) {
+                FrameOutcome::Incomplete => break,
+                FrameOutcome::Decoded(record, frame_len) => {
+                    records.push(record);
+                    consumed += frame_len;
+                }
+                FrameOutcome::Desync(skip, reason) => {
+                    log::warn!(
+                        "defmt: dropping {} byte(s) while resynchronizing RTT stream: {}",
+                        skip,
+                        reason
+                    );
+                    consumed += skip;
+                }
+            }
+        }
+
+        self.buffer.drain(..consumed);
+
+        Ok(records)
+    }
+
+    /// Attempts to decode a single frame (`index`, `timestamp`, `args...`) from
+    /// the front of `bytes`.
+    fn decode_frame(&self, bytes: &[u8]) -> FrameOutcome {
+        let mut cursor = 0;
+
+        let index = match read_leb128(bytes, &mut cursor) {
+            Some(index) => index,
+            None => return FrameOutcome::Incomplete,
+        };
+        let timestamp = match read_leb128(bytes, &mut cursor) {
+            Some(timestamp) => timestamp,
+            None => return FrameOutcome::Incomplete,
+        };
+
+        let entry = match self.table.lookup(index) {
+            Some(entry) => entry,
+            None => {
+                return FrameOutcome::Desync(1, format!("unknown defmt format string index {}", index))
+            }
+        };
+
+        match format_args(&entry.format, &bytes[cursor..]) {
+            Ok(Some((message, args_len))) => FrameOutcome::Decoded(
+                DefmtRecord {
+                    level: entry.level,
+                    timestamp,
+                    message,
+                },
+                cursor + args_len,
+            ),
+            Ok(None) => FrameOutcome::Incomplete,
+            Err(e) => FrameOutcome::Desync(1, e.to_string()),
+        }
+    }
+}
+
+/// The result of attempting to decode a single frame from the front of the
+/// decoder's buffer.
+#[derive(Debug)]
+enum FrameOutcome {
+    /// Not enough bytes are buffered yet; wait for more to arrive.
+    Incomplete,
+    /// A full frame decoded successfully.
+    Decoded(DefmtRecord, usize),
+    /// The bytes at the front of the buffer don't form a valid frame. The
+    /// caller should skip the given number of bytes and try again, rather than
+    /// treating this as fatal.
+    Desync(usize, String),
+}
+
+/// Reads a leb128-encoded `u64` starting at `*cursor`, advancing `*cursor` past
+/// it. Returns `None` (without advancing) if `bytes` ends mid-encoding.
+fn read_leb128(bytes: &[u8], cursor: &mut usize) -> Option<u64> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    let mut pos = *cursor;
+
+    loop {
+        let byte = *bytes.get(pos)?;
+        pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            *cursor = pos;
+            return Some(result);
+        }
+
+        shift += 7;
+    }
+}
+
+/// Substitutes each `{=TYPE}` placeholder in `format` (`defmt`'s actual tag
+/// syntax, e.g. `{=u32}`, `{=str}`) with the next argument decoded from `bytes`
+/// per its declared type, returning the formatted message and how many bytes
+/// of `bytes` the arguments consumed.
+///
+/// Returns `Ok(None)` if `bytes` doesn't yet hold enough data for every
+/// argument — the caller should wait for more bytes from the target rather
+/// than treating this as corrupt — and `Err` if `format` contains a type tag
+/// we don't know how to decode or an argument's bytes are otherwise invalid
+/// (e.g. a `str` argument that isn't valid UTF-8).
+fn format_args(format: &str, bytes: &[u8]) -> Result<Option<(String, usize)>, Error> {
+    let mut cursor = 0;
+    let mut message = String::with_capacity(format.len());
+    let mut rest = format;
+
+    while let Some(open) = rest.find("{=") {
+        let close = match rest[open..].find('}') {
+            Some(close) => open + close,
+            None => {
+                return Err(Error::Rtt(anyhow!(
+                    "Unterminated defmt type tag in format string {:?}",
+                    format
+                )))
+            }
+        };
+        let tag = &rest[open + 2..close];
+
+        message.push_str(&rest[..open]);
+
+        match decode_arg(tag, bytes, &mut cursor) {
+            Some(Ok(rendered)) => message.push_str(&rendered),
+            Some(Err(e)) => return Err(e),
+            None => return Ok(None),
+        }
+
+        rest = &rest[close + 1..];
+    }
+    message.push_str(rest);
+
+    Ok(Some((message, cursor)))
+}
+
+/// Decodes a single `defmt` argument of the type named by `tag` from the front
+/// of `bytes[*cursor..]`, advancing `*cursor` past it.
+///
+/// Returns `None` if `bytes` doesn't yet hold enough data for the argument,
+/// `Some(Err(_))` for an unsupported tag or invalid data, and `Some(Ok(_))`
+/// with the argument rendered for display otherwise.
+fn decode_arg(tag: &str, bytes: &[u8], cursor: &mut usize) -> Option<Result<String, Error>> {
+    match tag {
+        "u8" | "u16" | "u32" | "u64" | "usize" => {
+            let value = read_leb128(bytes, cursor)?;
+            Some(Ok(value.to_string()))
+        }
+        "i8" | "i16" | "i32" | "i64" | "isize" => {
+            let encoded = read_leb128(bytes, cursor)?;
+            Some(Ok(zigzag_decode(encoded).to_string()))
+        }
+        "bool" => {
+            let byte = *bytes.get(*cursor)?;
+            *cursor += 1;
+            Some(Ok((byte != 0).to_string()))
+        }
+        "f32" => {
+            let raw: [u8; 4] = bytes.get(*cursor..*cursor + 4)?.try_into().ok()?;
+            *cursor += 4;
+            Some(Ok(f32::from_le_bytes(raw).to_string()))
+        }
+        "f64" => {
+            let raw: [u8; 8] = bytes.get(*cursor..*cursor + 8)?.try_into().ok()?;
+            *cursor += 8;
+            Some(Ok(f64::from_le_bytes(raw).to_string()))
+        }
+        "str" => {
+            let len = read_leb128(bytes, cursor)? as usize;
+            let raw = bytes.get(*cursor..*cursor + len)?;
+            match std::str::from_utf8(raw) {
+                Ok(s) => {
+                    *cursor += len;
+                    Some(Ok(s.to_string()))
+                }
+                Err(e) => Some(Err(Error::Rtt(anyhow!(
+                    "Invalid UTF-8 in defmt str argument: {}",
+                    e
+                )))),
+            }
+        }
+        other => Some(Err(Error::Rtt(anyhow!(
+            "Unsupported defmt type tag {{={}}}",
+            other
+        )))),
+    }
+}
+
+/// Decodes a zigzag-encoded signed integer, as `defmt` uses for signed
+/// argument types so small negative numbers stay compact under leb128.
+fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leb128_single_byte() {
+        let mut cursor = 0;
+        assert_eq!(read_leb128(&[0x7f], &mut cursor), Some(0x7f));
+        assert_eq!(cursor, 1);
+    }
+
+    #[test]
+    fn leb128_multi_byte() {
+        let mut cursor = 0;
+        // 300 encodes as 0xAC 0x02.
+        assert_eq!(read_leb128(&[0xac, 0x02], &mut cursor), Some(300));
+        assert_eq!(cursor, 2);
+    }
+
+    #[test]
+    fn leb128_truncated_does_not_advance_cursor() {
+        let mut cursor = 0;
+        assert_eq!(read_leb128(&[0x80], &mut cursor), None);
+        assert_eq!(cursor, 0);
+    }
+
+    #[test]
+    fn zigzag_decode_round_trips_small_negatives() {
+        assert_eq!(zigzag_decode(0), 0);
+        assert_eq!(zigzag_decode(1), -1);
+        assert_eq!(zigzag_decode(2), 1);
+        assert_eq!(zigzag_decode(3), -2);
+    }
+
+    #[test]
+    fn format_args_decodes_u32() {
+        let bytes = [0xac, 0x02]; // 300
+        let (message, len) = format_args("count = {=u32}", &bytes).unwrap().unwrap();
+        assert_eq!(message, "count = 300");
+        assert_eq!(len, 2);
+    }
+
+    #[test]
+    fn format_args_decodes_signed_and_str() {
+        // i32 argument -1 (zigzag 1), then str "hi" (len-prefixed).
+        let bytes = [0x01, 0x02, b'h', b'i'];
+        let (message, len) = format_args("{=i32} {=str}", &bytes).unwrap().unwrap();
+        assert_eq!(message, "-1 hi");
+        assert_eq!(len, 4);
+    }
+
+    #[test]
+    fn format_args_incomplete_returns_none() {
+        let bytes = [0x80]; // truncated leb128
+        assert!(format_args("count = {=u32}", &bytes).unwrap().is_none());
+    }
+
+    #[test]
+    fn format_args_unknown_tag_errors() {
+        let bytes = [0x00];
+        assert!(format_args("{=weird}", &bytes).is_err());
+    }
+
+    #[test]
+    fn format_args_no_placeholders_consumes_nothing() {
+        let (message, len) = format_args("just text", &[]).unwrap().unwrap();
+        assert_eq!(message, "just text");
+        assert_eq!(len, 0);
+    }
+}
@@ -0,0 +1,339 @@
+//! Support for SEGGER's Real-Time Transfer (RTT) protocol.
+//!
+//! RTT works by placing a control block in target RAM that describes a set of
+//! "up" (target to host) and "down" (host to target) ring buffers. Because the
+//! location of the control block is chosen by the target application at build
+//! time, we find it by scanning RAM for its 16-byte ASCII signature rather than
+//! requiring the caller to know the address up front.
+//!
+//! This lets tools built on probe-rs stream log output the way `defmt`-based
+//! firmware relies on, without any architecture-specific handling: all access
+//! goes through [`Core`], so it works the same on ARM and RISC-V targets.
+
+use crate::config::MemoryRegion;
+use crate::{Core, Error};
+use anyhow::anyhow;
+
+pub mod defmt;
+
+/// The ASCII signature marking the start of an RTT control block in target RAM.
+const RTT_ID: &[u8; 16] = b"SEGGER RTT\0\0\0\0\0\0";
+
+/// Size in bytes of the control block header (`id` plus the two channel counts).
+const HEADER_SIZE: u32 = 16 + 4 + 4;
+
+/// Size in bytes of a single channel descriptor (`name_ptr`, `buffer_ptr`, `size`,
+/// `write_offset`, `read_offset`, `flags`), each a little-endian `u32`.
+const CHANNEL_DESCRIPTOR_SIZE: u32 = 4 * 6;
+
+/// Byte offset of the `name_ptr` field within a channel descriptor.
+const NAME_PTR_OFFSET: u32 = 0;
+/// Byte offset of the `buffer_ptr` field within a channel descriptor.
+const BUFFER_PTR_OFFSET: u32 = 4;
+/// Byte offset of the `size` field within a channel descriptor.
+const SIZE_OFFSET: u32 = 8;
+/// Byte offset of the `write_offset` field within a channel descriptor.
+const WRITE_OFFSET_OFFSET: u32 = 12;
+/// Byte offset of the `read_offset` field within a channel descriptor.
+const READ_OFFSET_OFFSET: u32 = 16;
+
+/// How many bytes to read per scan step when searching RAM for the control block.
+const SCAN_CHUNK_SIZE: u32 = 1024;
+
+/// Upper bound on `max_up_channels`/`max_down_channels` taken from a control
+/// block. Real RTT setups use a handful of channels; a value above this is far
+/// more likely to be a stale control block or an incidental signature match
+/// elsewhere in RAM than a real channel count, so it's rejected outright rather
+/// than trusted into an allocation and a long read loop.
+const MAX_CHANNELS: u32 = 64;
+
+/// Longest channel name we'll read from the target, in bytes. Channel names
+/// are short, human-chosen labels (`"defmt"`, `"Terminal"`); anything longer
+/// most likely means we're reading from the wrong address, so the string is
+/// truncated rather than read without bound.
+const MAX_NAME_LEN: usize = 32;
+
+/// A handle to an RTT control block found on the target, along with the up and
+/// down channels it describes.
+#[derive(Debug)]
+pub struct Rtt {
+    ptr: u32,
+    pub up_channels: Vec<UpChannel>,
+    pub down_channels: Vec<DownChannel>,
+}
+
+impl Rtt {
+    /// Scans the RAM regions of `memory_map` for an RTT control block and attaches
+    /// to it.
+    ///
+    /// Returns [`Error::Rtt`] if no control block could be found.
+    pub fn attach(core: &mut Core, memory_map: &[MemoryRegion]) -> Result<Rtt, Error> {
+        let ptr = scan_for_control_block(core, memory_map)?
+            .ok_or_else(|| Error::Rtt(anyhow!("No RTT control block found in target RAM")))?;
+
+        let max_up_channels = core.read_word_32(ptr + 16)?;
+        let max_down_channels = core.read_word_32(ptr + 20)?;
+
+        if max_up_channels > MAX_CHANNELS || max_down_channels > MAX_CHANNELS {
+            return Err(Error::Rtt(anyhow!(
+                "RTT control block at {:#x} reports implausible channel counts ({} up, {} down); \
+                 refusing to trust it",
+                ptr,
+                max_up_channels,
+                max_down_channels
+            )));
+        }
+
+        let mut address = ptr + HEADER_SIZE;
+
+        let mut up_channels = Vec::with_capacity(max_up_channels as usize);
+        for _ in 0..max_up_channels {
+            up_channels.push(UpChannel(Channel::read(core, address)?));
+            address += CHANNEL_DESCRIPTOR_SIZE;
+        }
+
+        let mut down_channels = Vec::with_capacity(max_down_channels as usize);
+        for _ in 0..max_down_channels {
+            down_channels.push(DownChannel(Channel::read(core, address)?));
+            address += CHANNEL_DESCRIPTOR_SIZE;
+        }
+
+        Ok(Rtt {
+            ptr,
+            up_channels,
+            down_channels,
+        })
+    }
+
+    /// The address of the control block on the target.
+    pub fn ptr(&self) -> u32 {
+        self.ptr
+    }
+}
+
+/// The fields of an RTT channel descriptor that change at runtime, plus the
+/// descriptor's own address so they can be re-read and written back.
+#[derive(Debug, Clone)]
+struct Channel {
+    /// Address of the channel descriptor itself, used to read/write its fields.
+    descriptor_address: u32,
+    /// The channel's firmware-assigned name (e.g. `"defmt"`, `"Terminal"`), if
+    /// `name_ptr` was non-null and pointed at readable memory.
+    name: Option<String>,
+    buffer_ptr: u32,
+    size: u32,
+    write_offset: u32,
+    read_offset: u32,
+}
+
+impl Channel {
+    fn read(core: &mut Core, descriptor_address: u32) -> Result<Channel, Error> {
+        let name_ptr = core.read_word_32(descriptor_address + NAME_PTR_OFFSET)?;
+        let name = if name_ptr == 0 {
+            None
+        } else {
+            Some(read_c_str(core, name_ptr, MAX_NAME_LEN)?)
+        };
+
+        let size = core.read_word_32(descriptor_address + SIZE_OFFSET)?;
+        if size == 0 {
+            // A zero-size ring buffer has no valid read/write offsets and would
+            // underflow the wraparound arithmetic in `UpChannel`/`DownChannel`.
+            // This happens for a channel the target hasn't finished configuring
+            // yet (attach racing `RTT_Init`), so treat it as unusable rather
+            // than trusting it.
+            return Err(Error::Rtt(anyhow!(
+                "RTT channel descriptor at {:#x} has size 0",
+                descriptor_address
+            )));
+        }
+
+        Ok(Channel {
+            descriptor_address,
+            name,
+            buffer_ptr: core.read_word_32(descriptor_address + BUFFER_PTR_OFFSET)?,
+            size,
+            write_offset: core.read_word_32(descriptor_address + WRITE_OFFSET_OFFSET)?,
+            read_offset: core.read_word_32(descriptor_address + READ_OFFSET_OFFSET)?,
+        })
+    }
+}
+
+/// Reads a nul-terminated string from target memory starting at `address`,
+/// stopping after at most `max_len` bytes even if no nul byte was found.
+fn read_c_str(core: &mut Core, address: u32, max_len: usize) -> Result<String, Error> {
+    let mut bytes = Vec::new();
+    let mut byte = [0u8; 1];
+
+    for offset in 0..max_len as u32 {
+        core.read_8(address + offset, &mut byte)?;
+        if byte[0] == 0 {
+            break;
+        }
+        bytes.push(byte[0]);
+    }
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Number of unread bytes currently in a ring buffer of the given `size`.
+fn ring_buffer_available(size: u32, read_offset: u32, write_offset: u32) -> u32 {
+    if write_offset >= read_offset {
+        write_offset - read_offset
+    } else {
+        size - read_offset + write_offset
+    }
+}
+
+/// A target-to-host RTT channel.
+///
+/// `write_offset` is re-read from the target on every call so that we see
+/// writes the target has made since we last looked; `read_offset` is owned by
+/// the host and kept locally.
+#[derive(Debug)]
+pub struct UpChannel(Channel);
+
+impl UpChannel {
+    /// The channel's firmware-assigned name (e.g. `"defmt"`), if it has one.
+    ///
+    /// Channel ordering and count are decided by the firmware, so this is the
+    /// reliable way to pick out a specific channel (such as the one carrying
+    /// `defmt` frames) rather than guessing an index.
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// Reads as many bytes as are currently available into `buf`, returning the
+    /// number of bytes read.
+    ///
+    /// This advances the channel's `read_offset` on the target, so repeated
+    /// calls drain the ring buffer rather than re-reading the same data.
+    pub fn read(&mut self, core: &mut Core, buf: &mut [u8]) -> Result<usize, Error> {
+        let channel = &mut self.0;
+        channel.write_offset = core.read_word_32(channel.descriptor_address + WRITE_OFFSET_OFFSET)?;
+
+        let available = ring_buffer_available(channel.size, channel.read_offset, channel.write_offset);
+        let to_read = (available as usize).min(buf.len());
+        if to_read == 0 {
+            return Ok(0);
+        }
+
+        let until_wrap = (channel.size - channel.read_offset) as usize;
+        if to_read <= until_wrap {
+            core.read_8(channel.buffer_ptr + channel.read_offset, &mut buf[..to_read])?;
+        } else {
+            core.read_8(channel.buffer_ptr + channel.read_offset, &mut buf[..until_wrap])?;
+            core.read_8(channel.buffer_ptr, &mut buf[until_wrap..to_read])?;
+        }
+
+        channel.read_offset = (channel.read_offset + to_read as u32) % channel.size;
+        core.write_word_32(channel.descriptor_address + READ_OFFSET_OFFSET, channel.read_offset)?;
+
+        Ok(to_read)
+    }
+}
+
+/// A host-to-target RTT channel.
+#[derive(Debug)]
+pub struct DownChannel(Channel);
+
+impl DownChannel {
+    /// The channel's firmware-assigned name, if it has one. See
+    /// [`UpChannel::name`].
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// Writes as many bytes of `buf` as currently fit in the ring buffer,
+    /// returning the number of bytes written.
+    pub fn write(&mut self, core: &mut Core, buf: &[u8]) -> Result<usize, Error> {
+        let channel = &mut self.0;
+        channel.read_offset = core.read_word_32(channel.descriptor_address + READ_OFFSET_OFFSET)?;
+
+        let used = ring_buffer_available(channel.size, channel.read_offset, channel.write_offset);
+        let free = (channel.size - 1 - used) as usize;
+        let to_write = free.min(buf.len());
+        if to_write == 0 {
+            return Ok(0);
+        }
+
+        let until_wrap = (channel.size - channel.write_offset) as usize;
+        if to_write <= until_wrap {
+            core.write_8(channel.buffer_ptr + channel.write_offset, &buf[..to_write])?;
+        } else {
+            core.write_8(channel.buffer_ptr + channel.write_offset, &buf[..until_wrap])?;
+            core.write_8(channel.buffer_ptr, &buf[until_wrap..to_write])?;
+        }
+
+        channel.write_offset = (channel.write_offset + to_write as u32) % channel.size;
+        core.write_word_32(channel.descriptor_address + WRITE_OFFSET_OFFSET, channel.write_offset)?;
+
+        Ok(to_write)
+    }
+}
+
+/// Scans the RAM regions of `memory_map` for the RTT control block signature,
+/// returning its address if found.
+fn scan_for_control_block(
+    core: &mut Core,
+    memory_map: &[MemoryRegion],
+) -> Result<Option<u32>, Error> {
+    for region in memory_map {
+        let range = match region {
+            MemoryRegion::Ram(ram) => ram.range.clone(),
+            _ => continue,
+        };
+
+        // Read in overlapping chunks so a signature that straddles a chunk
+        // boundary is still found: each chunk after the first starts far enough
+        // back to include the tail of the previous one.
+        let overlap = RTT_ID.len() as u32 - 1;
+        let mut address = range.start;
+
+        while address < range.end {
+            let len = SCAN_CHUNK_SIZE.min(range.end - address);
+            let mut chunk = vec![0u8; len as usize];
+            core.read_8(address, &mut chunk)?;
+
+            if let Some(pos) = chunk
+                .windows(RTT_ID.len())
+                .position(|window| window == RTT_ID)
+            {
+                return Ok(Some(address + pos as u32));
+            }
+
+            if len <= overlap {
+                break;
+            }
+            address += len - overlap;
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_available_without_wrap() {
+        assert_eq!(ring_buffer_available(64, 4, 10), 6);
+    }
+
+    #[test]
+    fn ring_buffer_available_with_wrap() {
+        assert_eq!(ring_buffer_available(64, 60, 4), 8);
+    }
+
+    #[test]
+    fn ring_buffer_available_empty() {
+        assert_eq!(ring_buffer_available(64, 10, 10), 0);
+    }
+
+    #[test]
+    fn ring_buffer_available_full_minus_one() {
+        // write caught up to read from behind, i.e. the buffer is completely full.
+        assert_eq!(ring_buffer_available(64, 10, 9), 63);
+    }
+}
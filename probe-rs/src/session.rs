@@ -11,6 +11,7 @@ use crate::config::{
     ChipInfo, MemoryRegion, RawFlashAlgorithm, RegistryError, Target, TargetSelector,
 };
 use crate::core::{Architecture, CoreState, SpecificCoreState};
+use crate::rtt::Rtt;
 use crate::{AttachMethod, Core, CoreType, Error, Probe};
 use anyhow::anyhow;
 use std::time::Duration;
@@ -102,21 +103,30 @@ impl Session {
         };
 
         if attach_method == AttachMethod::UnderReset {
-            // Enable debug mode
-            debug_core_start(&mut session.core(0)?)?;
-
-            // we need to halt the chip here
-            reset_catch_set(&mut session.core(0)?)?;
+            // For multi-core chips, every core must have debug enabled and its
+            // reset-catch armed *before* the reset pin is deasserted, otherwise a
+            // core that isn't ready yet could start running before we can catch
+            // it. Only once every core is armed do we deassert reset and wait for
+            // each core to report halted.
+            for n in 0..session.cores.len() {
+                // Enable debug mode
+                debug_core_start(&mut session.core(n)?)?;
+
+                // we need to halt the chip here
+                reset_catch_set(&mut session.core(n)?)?;
+            }
 
             // Deassert the reset pin
             session.probe.target_reset_deassert()?;
 
-            // Wait for the core to be halted
-            let mut core = session.core(0)?;
+            for n in 0..session.cores.len() {
+                // Wait for the core to be halted
+                let mut core = session.core(n)?;
 
-            core.wait_for_core_halted(Duration::from_millis(100))?;
+                core.wait_for_core_halted(Duration::from_millis(100))?;
 
-            reset_catch_clear(&mut core)?;
+                reset_catch_clear(&mut core)?;
+            }
         }
 
         session.clear_all_hw_breakpoints()?;
@@ -184,6 +194,72 @@ impl Session {
             .collect::<Result<Vec<_>, _>>()
             .map(|_| ())
     }
+
+    /// Halts every core in the session.
+    pub fn halt_all(&mut self) -> Result<(), Error> {
+        for n in 0..self.cores.len() {
+            self.core(n)?.halt(Duration::from_millis(100))?;
+        }
+
+        Ok(())
+    }
+
+    /// Resumes every core in the session.
+    ///
+    /// Clears each core's reset-catch before resuming it, in case
+    /// [`Session::attach_under_reset_core`] left it armed — otherwise the catch
+    /// bit would stay set and silently re-halt the core on the next unrelated
+    /// reset (e.g. a later `UnderReset` attach, or a watchdog reset).
+    pub fn resume_all(&mut self) -> Result<(), Error> {
+        for n in 0..self.cores.len() {
+            let mut core = self.core(n)?;
+            reset_catch_clear(&mut core)?;
+            core.run()?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the `UnderReset` attach sequence, but only releases `core_index`
+    /// from its reset-catch afterwards.
+    ///
+    /// This is useful on multi-core SoCs where only one core should start
+    /// running out of reset while the others stay held. `target_reset_deassert`
+    /// releases the chip's single, shared physical reset line, so every core
+    /// still needs its reset-catch armed beforehand — otherwise the cores other
+    /// than `core_index` would run completely free the moment reset is
+    /// deasserted. They're left halted with their catch still armed, so they
+    /// stay held until resumed via [`Session::resume_all`], which clears each
+    /// core's catch before running it. Calling [`Session::core`] alone does
+    /// not resume or clear anything — it just attaches a handle to the core,
+    /// which will still report halted.
+    pub fn attach_under_reset_core(&mut self, core_index: usize) -> Result<(), Error> {
+        for n in 0..self.cores.len() {
+            debug_core_start(&mut self.core(n)?)?;
+            reset_catch_set(&mut self.core(n)?)?;
+        }
+
+        self.probe.target_reset_deassert()?;
+
+        let mut core = self.core(core_index)?;
+        core.wait_for_core_halted(Duration::from_millis(100))?;
+        reset_catch_clear(&mut core)?;
+
+        Ok(())
+    }
+
+    /// Attaches to the target's RTT control block, enabling host/target logging
+    /// over the channels it describes.
+    ///
+    /// This scans the RAM regions of [`Session::memory_map`] for the control
+    /// block, so the target application must already be running with RTT
+    /// initialized (e.g. past the point where `rtt-target` or similar sets it up).
+    pub fn attach_rtt(&mut self) -> Result<Rtt, Error> {
+        let memory_map = self.target.memory_map.clone();
+        let mut core = self.core(0)?;
+
+        Rtt::attach(&mut core, &memory_map)
+    }
 }
 
 fn try_arm_autodetect(
@@ -201,6 +277,54 @@ fn try_arm_autodetect(
     Ok(found_chip)
 }
 
+/// Chip identification extracted from a RISC-V JTAG IDCODE, analogous to
+/// `ArmChipInfo` on the ARM side.
+///
+/// The IDCODE layout (RISC-V debug spec, section 6.1.3) is `[31:28] version`,
+/// `[27:12] part number`, `[11:1] JEDEC manufacturer id`, `[0]` fixed to `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiscvChipInfo {
+    pub manufacturer: jep106::JEP106Code,
+    pub part: u16,
+}
+
+impl From<RiscvChipInfo> for ChipInfo {
+    fn from(value: RiscvChipInfo) -> Self {
+        ChipInfo::Riscv(value)
+    }
+}
+
+fn try_riscv_autodetect(
+    riscv_interface: &mut RiscvCommunicationInterface,
+) -> Result<Option<ChipInfo>, Error> {
+    log::debug!("Autodetect: Trying JTAG interface...");
+
+    let idcode = riscv_interface.read_idcode()?;
+    log::debug!("ID Code read over JTAG: {:x?}", idcode);
+
+    Ok(Some(ChipInfo::from(riscv_chip_info_from_idcode(idcode))))
+}
+
+/// Extracts the JEDEC manufacturer id and part number from a RISC-V JTAG
+/// IDCODE (RISC-V debug spec, section 6.1.3): `[31:28] version`, `[27:12] part
+/// number`, `[11:1] JEDEC manufacturer id`, `[0]` fixed to `1`.
+///
+/// The manufacturer id is packed the same way as the ARM JEP-106 field: a
+/// continuation count in the upper 4 bits and the final identity byte in the
+/// lower 7.
+fn riscv_chip_info_from_idcode(idcode: u32) -> RiscvChipInfo {
+    let manufacturer_id = ((idcode >> 1) & 0x7ff) as u16;
+    let continuation_count = (manufacturer_id >> 7) as u8;
+    let identity_code = (manufacturer_id & 0x7f) as u8;
+
+    let part = ((idcode >> 12) & 0xffff) as u16;
+
+    RiscvChipInfo {
+        manufacturer: jep106::JEP106Code::new(continuation_count, identity_code),
+        part,
+    }
+}
+
 impl Drop for Session {
     fn drop(&mut self) {
         if let Err(err) = self.clear_all_hw_breakpoints() {
@@ -242,14 +366,16 @@ fn get_target_from_selector(
                 let interface = RiscvCommunicationInterface::new(probe, &mut state)?;
 
                 if let Some(mut interface) = interface {
-                    let idcode = interface.read_idcode();
+                    let chip_result = try_riscv_autodetect(&mut interface);
 
-                    log::debug!("ID Code read over JTAG: {:x?}", idcode);
+                    // Ignore errors during autodetect
+                    found_chip = chip_result.unwrap_or_else(|e| {
+                        log::debug!("An error occured during RISC-V autodetect: {}", e);
+                        None
+                    });
                 } else {
                     log::debug!("No JTAG interface was present. Skipping Riscv autodetect.");
                 }
-
-                // TODO: Implement autodetect for RISC-V
             }
 
             if let Some(chip) = found_chip {
@@ -262,3 +388,31 @@ fn get_target_from_selector(
 
     Ok(target)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn riscv_chip_info_extracts_manufacturer_and_part() {
+        // version = 1, part = 0x1234, manufacturer: continuation count 0x3,
+        // identity code 0x45, fixed low bit = 1.
+        let manufacturer_id = (0x3u32 << 7) | 0x45;
+        let idcode = (1 << 28) | (0x1234 << 12) | (manufacturer_id << 1) | 1;
+
+        let chip = riscv_chip_info_from_idcode(idcode);
+
+        assert_eq!(chip.part, 0x1234);
+        assert_eq!(chip.manufacturer, jep106::JEP106Code::new(0x3, 0x45));
+    }
+
+    #[test]
+    fn riscv_chip_info_handles_zero_manufacturer() {
+        let idcode = (0x0001 << 12) | 1;
+
+        let chip = riscv_chip_info_from_idcode(idcode);
+
+        assert_eq!(chip.part, 0x0001);
+        assert_eq!(chip.manufacturer, jep106::JEP106Code::new(0, 0));
+    }
+}